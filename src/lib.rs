@@ -0,0 +1,3 @@
+pub mod pool;
+pub mod sftp;
+pub mod ssh2;
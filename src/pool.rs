@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+
+use crate::ssh2::{create_session_with, run_command, Auth, HostKeyCheck, Session};
+
+/// Authentication to use for a pooled host. Mirrors [`Auth`] but owns its data so a [`Host`] can
+/// be reconnected from scratch without the caller keeping anything alive. Keyboard-interactive
+/// authentication has no counterpart here.
+#[derive(Clone)]
+pub enum AuthSpec {
+    PublicKey { privatekey: PathBuf, passphrase: Option<String> },
+    Password(String),
+    Agent,
+}
+
+impl AuthSpec {
+    fn to_auth(&self) -> Auth<'_> {
+        match self {
+            AuthSpec::PublicKey { privatekey, passphrase } => {
+                Auth::PublicKey { privatekey, passphrase: passphrase.as_deref() }
+            }
+            AuthSpec::Password(password) => Auth::Password(password.clone()),
+            AuthSpec::Agent => Auth::Agent,
+        }
+    }
+}
+
+/// A remote host reachable through a [`SessionPool`].
+#[derive(Clone)]
+pub struct Host {
+    pub ip: IpAddr,
+    pub port: u16,
+    pub username: String,
+    pub auth: AuthSpec,
+}
+
+impl Host {
+    fn key(&self) -> String {
+        format!("{}@{}:{}", self.username, self.ip, self.port)
+    }
+}
+
+/// A pool of authenticated sessions keyed by host, reused across calls instead of reconnecting
+/// every time. Dead sessions are detected with a cheap probe command and transparently replaced.
+///
+/// `ssh2::Session` is `Send`-safe via an internal `Arc<Mutex<>>` over the native handle, so a
+/// cloned session can be handed to another thread while the pool keeps its own copy.
+pub struct SessionPool {
+    sessions: Mutex<HashMap<String, Session>>,
+    host_key_check: HostKeyCheck,
+}
+
+impl SessionPool {
+    pub fn new(host_key_check: HostKeyCheck) -> Self {
+        Self { sessions: Mutex::new(HashMap::new()), host_key_check }
+    }
+
+    /// Returns a live, authenticated session for `host`, creating or reconnecting as needed.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if connecting or authenticating to `host` fails.
+    pub fn get(&self, host: &Host) -> anyhow::Result<Session> {
+        let key = host.key();
+        let cached = self.sessions.lock().unwrap().get(&key).cloned();
+
+        if let Some(sess) = cached {
+            if Self::is_alive(&sess) {
+                return Ok(sess);
+            }
+        }
+
+        let sess = create_session_with(host.ip, host.port, &host.username, host.auth.to_auth(), self.host_key_check)?;
+
+        // Another thread may have raced us and already inserted a live session for this host;
+        // prefer it over ours so concurrent misses settle on a single new connection. But if the
+        // racing entry is itself dead (e.g. it was the stale session we just replaced), overwrite
+        // it with ours rather than keeping a connection nobody can use.
+        let mut sessions = self.sessions.lock().unwrap();
+        let sess = match sessions.get(&key) {
+            Some(existing) if Self::is_alive(existing) => existing.clone(),
+            _ => {
+                sessions.insert(key, sess.clone());
+                sess
+            }
+        };
+        Ok(sess)
+    }
+
+    /// Drops the cached session for `host`, if any, forcing a reconnect on the next `get`.
+    pub fn evict(&self, host: &Host) {
+        self.sessions.lock().unwrap().remove(&host.key());
+    }
+
+    fn is_alive(sess: &Session) -> bool {
+        sess.authenticated() && run_command(sess, "true").is_ok()
+    }
+
+    /// Runs `op` against every host in `hosts` concurrently, one OS thread per host, reusing or
+    /// establishing a pooled session for each. Returns each host's key paired with its result, in
+    /// the same order as `hosts`.
+    pub fn fan_out<T, F>(&self, hosts: &[Host], op: F) -> Vec<(String, anyhow::Result<T>)>
+    where
+        F: Fn(&Session) -> anyhow::Result<T> + Send + Sync,
+        T: Send,
+    {
+        thread::scope(|scope| {
+            let handles: Vec<_> = hosts
+                .iter()
+                .map(|host| {
+                    let op = &op;
+                    scope.spawn(move || {
+                        let result = self.get(host).and_then(|sess| op(&sess));
+                        (host.key(), result)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        })
+    }
+}
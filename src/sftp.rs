@@ -0,0 +1,216 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use ssh2::{FileStat, Session};
+use thiserror::Error as ErrorProcMacro;
+
+const CHUNK_SIZE: usize = 32 * 1024;
+
+#[derive(Debug, ErrorProcMacro)]
+enum Error {
+    #[error("Open SFTP subsystem error: {0}")]
+    SftpInit(String),
+    #[error("Read local file {0} error: {1}")]
+    ReadLocalFile(PathBuf, String),
+    #[error("Read local directory {0} error: {1}")]
+    ReadLocalDir(PathBuf, String),
+    #[error("Create remote file {0} error: {1}")]
+    CreateRemoteFile(PathBuf, String),
+    #[error("Read remote file {0} error: {1}")]
+    ReadRemoteFile(PathBuf, String),
+    #[error("Write local file {0} error: {1}")]
+    WriteLocalFile(PathBuf, String),
+    #[error("Create remote directory {0} error: {1}")]
+    MkdirRemote(PathBuf, String),
+    #[error("Create local directory {0} error: {1}")]
+    MkdirLocal(PathBuf, String),
+    #[error("List remote directory {0} error: {1}")]
+    ReadRemoteDir(PathBuf, String),
+    #[error("Stat remote path {0} error: {1}")]
+    StatRemote(PathBuf, String),
+    #[error("Remove remote path {0} error: {1}")]
+    RemoveRemote(PathBuf, String),
+    #[error("Rename remote path {0} to {1} error: {2}")]
+    RenameRemote(PathBuf, PathBuf, String),
+}
+
+/// Returns metadata for a remote path, including the Unix permission bits, over SFTP.
+///
+/// # Errors
+///
+/// This function will return an error if the SFTP subsystem fails to open or the remote path
+/// cannot be stat'd (e.g. it does not exist).
+pub fn stat(sess: &Session, remote_path: &Path) -> anyhow::Result<FileStat> {
+    let sftp = sess.sftp().map_err(|e| Error::SftpInit(e.to_string()))?;
+    sftp.stat(remote_path)
+        .map_err(|e| Error::StatRemote(remote_path.into(), e.to_string()).into())
+}
+
+/// Creates a remote directory and any missing parent directories, mirroring `mkdir -p`.
+///
+/// # Errors
+///
+/// This function will return an error if the SFTP subsystem fails to open, or if creating any
+/// path component fails for a reason other than it already existing.
+pub fn mkdir_p(sess: &Session, remote_dir: &Path) -> anyhow::Result<()> {
+    let sftp = sess.sftp().map_err(|e| Error::SftpInit(e.to_string()))?;
+    let mut built = PathBuf::new();
+    for component in remote_dir.components() {
+        built.push(component);
+        if sftp.stat(&built).is_ok() {
+            continue;
+        }
+        sftp.mkdir(&built, 0o755)
+            .map_err(|e| Error::MkdirRemote(built.clone(), e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Removes a single remote file.
+///
+/// # Errors
+///
+/// This function will return an error if the SFTP subsystem fails to open or the remote file
+/// cannot be removed.
+pub fn remove(sess: &Session, remote_file: &Path) -> anyhow::Result<()> {
+    let sftp = sess.sftp().map_err(|e| Error::SftpInit(e.to_string()))?;
+    sftp.unlink(remote_file)
+        .map_err(|e| Error::RemoveRemote(remote_file.into(), e.to_string()).into())
+}
+
+/// Renames (or moves) a remote path.
+///
+/// # Errors
+///
+/// This function will return an error if the SFTP subsystem fails to open or the rename fails,
+/// for example because `to` already exists.
+pub fn rename(sess: &Session, from: &Path, to: &Path) -> anyhow::Result<()> {
+    let sftp = sess.sftp().map_err(|e| Error::SftpInit(e.to_string()))?;
+    sftp.rename(from, to, None)
+        .map_err(|e| Error::RenameRemote(from.into(), to.into(), e.to_string()).into())
+}
+
+/// Recursively uploads a local directory to a remote directory, preserving Unix permissions.
+///
+/// The remote tree is recreated with `mkdir_p` and each regular file is streamed through an
+/// `sftp.create` handle in fixed-size chunks rather than being loaded into memory whole.
+///
+/// # Errors
+///
+/// This function will return an error if the SFTP subsystem fails to open, if walking the local
+/// directory fails, or if creating a remote directory or file fails. Errors include the offending
+/// local or remote path for context.
+pub fn upload_dir(sess: &Session, local_dir: &Path, remote_dir: &Path) -> anyhow::Result<()> {
+    let sftp = sess.sftp().map_err(|e| Error::SftpInit(e.to_string()))?;
+    mkdir_p(sess, remote_dir)?;
+
+    let entries = fs::read_dir(local_dir).map_err(|e| Error::ReadLocalDir(local_dir.into(), e.to_string()))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| Error::ReadLocalDir(local_dir.into(), e.to_string()))?;
+        let local_path = entry.path();
+        let remote_path = remote_dir.join(entry.file_name());
+        let file_type = entry.file_type().map_err(|e| Error::ReadLocalDir(local_path.clone(), e.to_string()))?;
+
+        if file_type.is_dir() {
+            upload_dir(sess, &local_path, &remote_path)?;
+            continue;
+        }
+
+        let mut local_file = fs::File::open(&local_path).map_err(|e| Error::ReadLocalFile(local_path.clone(), e.to_string()))?;
+        let mode = permissions_mode(&local_path)?;
+
+        let mut remote_file = sftp
+            .create(&remote_path)
+            .map_err(|e| Error::CreateRemoteFile(remote_path.clone(), e.to_string()))?;
+        let mut buf = [0u8; CHUNK_SIZE];
+        loop {
+            let n = local_file.read(&mut buf).map_err(|e| Error::ReadLocalFile(local_path.clone(), e.to_string()))?;
+            if n == 0 {
+                break;
+            }
+            remote_file
+                .write_all(&buf[..n])
+                .map_err(|e| Error::CreateRemoteFile(remote_path.clone(), e.to_string()))?;
+        }
+        sftp.setstat(&remote_path, FileStat { size: None, uid: None, gid: None, perm: Some(mode), atime: None, mtime: None })
+            .map_err(|e| Error::CreateRemoteFile(remote_path.clone(), e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Recursively downloads a remote directory to a local directory, preserving Unix permissions.
+///
+/// The remote tree is walked with `sftp.readdir` and each regular file is streamed through an
+/// `sftp.open` handle in fixed-size chunks rather than being loaded into memory whole.
+///
+/// # Errors
+///
+/// This function will return an error if the SFTP subsystem fails to open, if listing a remote
+/// directory fails, or if creating a local directory or file fails. Errors include the offending
+/// remote or local path for context.
+pub fn download_dir(sess: &Session, remote_dir: &Path, local_dir: &Path) -> anyhow::Result<()> {
+    let sftp = sess.sftp().map_err(|e| Error::SftpInit(e.to_string()))?;
+    fs::create_dir_all(local_dir).map_err(|e| Error::MkdirLocal(local_dir.into(), e.to_string()))?;
+
+    let entries = sftp
+        .readdir(remote_dir)
+        .map_err(|e| Error::ReadRemoteDir(remote_dir.into(), e.to_string()))?;
+    for (remote_path, stat) in entries {
+        let Some(name) = remote_path.file_name() else { continue };
+        if name == "." || name == ".." {
+            continue;
+        }
+        let local_path = local_dir.join(name);
+
+        if stat.is_dir() {
+            download_dir(sess, &remote_path, &local_path)?;
+            continue;
+        }
+
+        let mut remote_file = sftp
+            .open(&remote_path)
+            .map_err(|e| Error::ReadRemoteFile(remote_path.clone(), e.to_string()))?;
+        let mut local_file = fs::File::create(&local_path).map_err(|e| Error::WriteLocalFile(local_path.clone(), e.to_string()))?;
+        let mut buf = [0u8; CHUNK_SIZE];
+        loop {
+            let n = remote_file
+                .read(&mut buf)
+                .map_err(|e| Error::ReadRemoteFile(remote_path.clone(), e.to_string()))?;
+            if n == 0 {
+                break;
+            }
+            local_file
+                .write_all(&buf[..n])
+                .map_err(|e| Error::WriteLocalFile(local_path.clone(), e.to_string()))?;
+        }
+        if let Some(mode) = stat.perm {
+            set_permissions_mode(&local_path, mode)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn permissions_mode(path: &Path) -> anyhow::Result<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    let meta = fs::metadata(path).map_err(|e| Error::ReadLocalFile(path.into(), e.to_string()))?;
+    Ok(meta.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn permissions_mode(_path: &Path) -> anyhow::Result<u32> {
+    Ok(0o644)
+}
+
+#[cfg(unix)]
+fn set_permissions_mode(path: &Path, mode: u32) -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .map_err(|e| Error::WriteLocalFile(path.into(), e.to_string()).into())
+}
+
+#[cfg(not(unix))]
+fn set_permissions_mode(_path: &Path, _mode: u32) -> anyhow::Result<()> {
+    Ok(())
+}
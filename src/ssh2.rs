@@ -1,10 +1,15 @@
 use std::io::{Read, Write};
-use std::net::{IpAddr, TcpStream};
+use std::net::{IpAddr, TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
 
 pub use ssh2::Session;
+use ssh2::KeyboardInteractivePrompt;
 use thiserror::Error as ErrorProcMacro;
 
+const CHUNK_SIZE: usize = 32 * 1024;
+
 #[derive(Debug, ErrorProcMacro)]
 enum Error {
     #[error("Tcp stream connection to {0}:{1} error: {2}")]
@@ -15,6 +20,28 @@ enum Error {
     SessionHandshake(String),
     #[error("Session auth user {0} with private key file {1} error: {2}")]
     SessionUserAuth(String, PathBuf, String),
+    #[error("Session auth user {0} with password error: {1}")]
+    SessionPasswordAuth(String, String),
+    #[error("Session auth user {0} via ssh-agent error: {1}")]
+    SessionAgentAuth(String, String),
+    #[error("Session auth user {0} via keyboard-interactive error: {1}")]
+    SessionKeyboardInteractiveAuth(String, String),
+    #[error("Session auth user {0} did not succeed")]
+    AuthFailed(String),
+    #[error("Could not determine the current user's home directory to locate known_hosts")]
+    HomeDirUnavailable,
+    #[error("Loading known_hosts file {0} error: {1}")]
+    KnownHostsRead(PathBuf, String),
+    #[error("Writing known_hosts file {0} error: {1}")]
+    KnownHostsWrite(PathBuf, String),
+    #[error("Host {0} did not present a host key")]
+    HostKeyMissing(String),
+    #[error("Host key for {0} does not match the known_hosts entry")]
+    HostKeyMismatch(String),
+    #[error("Host key for {0} is not present in known_hosts: {1}")]
+    HostKeyUnknown(String, String),
+    #[error("Opening direct-tcpip channel to {0}:{1} error: {2}")]
+    JumpChannel(IpAddr, u16, String),
     #[error("Create remote file {0} error: {1}")]
     CreateRemoteFile(PathBuf, String),
     #[error("Write remote file {0} error: {1}")]
@@ -23,6 +50,69 @@ enum Error {
     ExecCommands(String, String),
 }
 
+/// Authentication methods accepted by [`create_session_with`].
+pub enum Auth<'a> {
+    /// Authenticate with a private key file, mirroring `userauth_pubkey_file`.
+    PublicKey {
+        privatekey: &'a Path,
+        passphrase: Option<&'a str>,
+    },
+    /// Authenticate with a plaintext password.
+    Password(String),
+    /// Authenticate against a running `ssh-agent`, trying each identity it offers.
+    Agent,
+    /// Authenticate via keyboard-interactive, driven by a caller-supplied prompt handler.
+    KeyboardInteractive(Box<dyn KeyboardInteractivePrompt>),
+}
+
+/// Policy applied to the remote host key before authenticating, checked against
+/// `~/.ssh/known_hosts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostKeyCheck {
+    /// Reject the connection if the host key is unknown or does not match the known_hosts entry.
+    Strict,
+    /// Accept and remember host keys seen for the first time, but reject changed keys.
+    AcceptNew,
+    /// Skip host key verification entirely (previous behavior).
+    Disabled,
+}
+
+/// Verifies the session's remote host key against `~/.ssh/known_hosts` according to `policy`.
+fn verify_host_key(sess: &Session, host: &str, port: u16, policy: HostKeyCheck) -> anyhow::Result<()> {
+    if policy == HostKeyCheck::Disabled {
+        return Ok(());
+    }
+
+    let (key, key_type) = sess.host_key().ok_or_else(|| Error::HostKeyMissing(host.to_string()))?;
+    let mut known_hosts = sess.known_hosts().map_err(|e| Error::KnownHostsRead(PathBuf::new(), e.to_string()))?;
+    let home_dir = home::home_dir().ok_or(Error::HomeDirUnavailable)?;
+    let known_hosts_path = home_dir.join(".ssh").join("known_hosts");
+    // A fresh machine has no known_hosts yet; treat that as an empty file rather than an
+    // error so `AcceptNew` (and a first `Strict` connection) can proceed to the `NotFound` path.
+    if known_hosts_path.exists() {
+        known_hosts
+            .read_file(&known_hosts_path, ssh2::KnownHostFileKind::OpenSSH)
+            .map_err(|e| Error::KnownHostsRead(known_hosts_path.clone(), e.to_string()))?;
+    }
+
+    match known_hosts.check_port(host, port, key) {
+        ssh2::CheckResult::Match => Ok(()),
+        ssh2::CheckResult::NotFound => match policy {
+            HostKeyCheck::AcceptNew => {
+                known_hosts
+                    .add(host, key, "added by minimalist", key_type.into())
+                    .map_err(|e| Error::HostKeyUnknown(host.to_string(), e.to_string()))?;
+                known_hosts
+                    .write_file(&known_hosts_path, ssh2::KnownHostFileKind::OpenSSH)
+                    .map_err(|e| Error::KnownHostsWrite(known_hosts_path.clone(), e.to_string()))?;
+                Ok(())
+            }
+            _ => Err(Error::HostKeyUnknown(host.to_string(), "host key not present in known_hosts".to_string()).into()),
+        },
+        ssh2::CheckResult::Mismatch | ssh2::CheckResult::Failure => Err(Error::HostKeyMismatch(host.to_string()).into()),
+    }
+}
+
 /// Establishes a new SSH session using the provided IP address, port, username, and private key file path.
 ///
 /// # Arguments
@@ -65,11 +155,38 @@ enum Error {
 /// }
 /// ```
 ///
-/// # Panics
+/// This is a thin wrapper over [`create_session_with`] using [`Auth::PublicKey`]; see that
+/// function for password, agent, and keyboard-interactive authentication.
+pub fn create_session(ip: IpAddr, port: u16, username: &str, privatekey: &Path) -> anyhow::Result<Session> {
+    create_session_with(ip, port, username, Auth::PublicKey { privatekey, passphrase: None }, HostKeyCheck::Disabled)
+}
+
+/// Establishes a new SSH session using the provided IP address, port, username, and
+/// authentication mode.
 ///
-/// This function will panic if the authentication is not successful after the `userauth_pubkey_file` call.
+/// # Arguments
 ///
-pub fn create_session(ip: IpAddr, port: u16, username: &str, privatekey: &Path) -> anyhow::Result<Session> {
+/// * `ip` - The IP address of the remote host to connect to.
+/// * `port` - The port number on the remote host to connect to.
+/// * `username` - The username to authenticate with on the remote host.
+/// * `auth` - The authentication mode to use, see [`Auth`].
+/// * `host_key_check` - The host key verification policy to apply before authenticating, see
+///   [`HostKeyCheck`].
+///
+/// # Returns
+///
+/// This function returns an `anyhow::Result<Session>`. On success, it contains a new,
+/// authenticated SSH session. On failure, it returns an error detailing what went wrong.
+///
+/// # Errors
+///
+/// This function will return an error in the following cases:
+/// * If the TCP connection to the specified `ip` and `port` fails.
+/// * If creating a new SSH session fails.
+/// * If the handshake process with the SSH server fails.
+/// * If the remote host key fails verification under `host_key_check`.
+/// * If the chosen authentication mode fails, or the session is not authenticated afterwards.
+pub fn create_session_with(ip: IpAddr, port: u16, username: &str, auth: Auth, host_key_check: HostKeyCheck) -> anyhow::Result<Session> {
     let tcp = TcpStream::connect(format!("{ip}:{port}"))
         .map_err(|e| Error::TcpStreamConnect(ip, port, e.to_string()))?;
     let mut sess = Session::new()
@@ -77,12 +194,191 @@ pub fn create_session(ip: IpAddr, port: u16, username: &str, privatekey: &Path)
     sess.set_tcp_stream(tcp);
     sess.handshake()
         .map_err(|e| Error::SessionHandshake(e.to_string()))?;
-    sess.userauth_pubkey_file(username, None, privatekey, None)
-        .map_err(|e| Error::SessionUserAuth(username.to_string(), privatekey.into(), e.to_string()))?;
-    assert!(sess.authenticated());
+
+    verify_host_key(&sess, &ip.to_string(), port, host_key_check)?;
+    authenticate(&sess, username, auth)?;
     Ok(sess)
 }
 
+/// Dispatches `auth` against an already handshaken session, then confirms authentication
+/// succeeded. Shared by [`create_session_with`] and [`create_session_via`].
+fn authenticate(sess: &Session, username: &str, auth: Auth) -> anyhow::Result<()> {
+    match auth {
+        Auth::PublicKey { privatekey, passphrase } => {
+            sess.userauth_pubkey_file(username, None, privatekey, passphrase)
+                .map_err(|e| Error::SessionUserAuth(username.to_string(), privatekey.into(), e.to_string()))?;
+        }
+        Auth::Password(password) => {
+            sess.userauth_password(username, &password)
+                .map_err(|e| Error::SessionPasswordAuth(username.to_string(), e.to_string()))?;
+        }
+        Auth::Agent => {
+            let mut agent = sess.agent().map_err(|e| Error::SessionAgentAuth(username.to_string(), e.to_string()))?;
+            agent.connect().map_err(|e| Error::SessionAgentAuth(username.to_string(), e.to_string()))?;
+            agent.list_identities().map_err(|e| Error::SessionAgentAuth(username.to_string(), e.to_string()))?;
+            let identities = agent.identities().map_err(|e| Error::SessionAgentAuth(username.to_string(), e.to_string()))?;
+            for identity in &identities {
+                if agent.userauth(username, identity).is_ok() && sess.authenticated() {
+                    break;
+                }
+            }
+        }
+        Auth::KeyboardInteractive(mut prompter) => {
+            // `userauth_keyboard_interactive` requires a `Sized` prompter; pass a `&mut` to the
+            // `Box` itself (which implements `KeyboardInteractivePrompt`) rather than unsizing it
+            // to `&mut dyn KeyboardInteractivePrompt` via `as_mut()`.
+            sess.userauth_keyboard_interactive(username, &mut prompter)
+                .map_err(|e| Error::SessionKeyboardInteractiveAuth(username.to_string(), e.to_string()))?;
+        }
+    }
+
+    if !sess.authenticated() {
+        return Err(Error::AuthFailed(username.to_string()).into());
+    }
+    Ok(())
+}
+
+/// Establishes a new SSH session to `target_ip:target_port` tunneled through an
+/// already-established `jump` session, mirroring OpenSSH's `ProxyJump`.
+///
+/// libssh2 drives its transport with a raw socket, so a `direct-tcpip` channel (multiplexed over
+/// `jump`'s own socket, not a socket of its own) can't be handed to [`Session::set_tcp_stream`]
+/// directly. [`relay_direct_tcpip`] opens the channel on a background thread instead and relays
+/// it to a loopback `TcpStream`, and the new `Session` is handshaken and authenticated over that
+/// loopback stream like any other.
+///
+/// # Arguments
+///
+/// * `jump` - An authenticated session on the bastion host.
+/// * `target_ip` - The IP address of the final host, as seen from the bastion.
+/// * `target_port` - The port number on the final host.
+/// * `username` - The username to authenticate with on the final host.
+/// * `auth` - The authentication mode to use against the final host, see [`Auth`].
+/// * `host_key_check` - The host key verification policy to apply to the final host's own key
+///   (checked against `~/.ssh/known_hosts` on the machine running this code, not the bastion),
+///   see [`HostKeyCheck`].
+///
+/// # Errors
+///
+/// This function will return an error in the following cases:
+/// * If the loopback relay for the `direct-tcpip` channel can't be set up.
+/// * If creating the new session or its handshake fails.
+/// * If the final host's key fails verification under `host_key_check`.
+/// * If the chosen authentication mode fails, or the session is not authenticated afterwards.
+pub fn create_session_via(jump: &Session, target_ip: IpAddr, target_port: u16, username: &str, auth: Auth, host_key_check: HostKeyCheck) -> anyhow::Result<Session> {
+    let stream = relay_direct_tcpip(jump.clone(), target_ip, target_port)?;
+
+    let mut sess = Session::new()
+        .map_err(|e| Error::SessionNew(e.to_string()))?;
+    sess.set_tcp_stream(stream);
+    sess.handshake()
+        .map_err(|e| Error::SessionHandshake(e.to_string()))?;
+
+    verify_host_key(&sess, &target_ip.to_string(), target_port, host_key_check)?;
+    authenticate(&sess, username, auth)?;
+    Ok(sess)
+}
+
+/// Opens a `direct-tcpip` channel to `target_ip:target_port` on `jump` and relays it to a
+/// loopback `TcpStream`, so a raw-socket API like [`Session::set_tcp_stream`] can be handshaken
+/// over it.
+///
+/// A background thread owns both the `jump` session (a clone, which shares the caller's
+/// underlying connection and keeps it alive; that shared session is in non-blocking mode while
+/// the relay runs) and the channel, and shuttles bytes between the channel and the accepted
+/// loopback connection until either side closes or errors. The thread winds down on its own once
+/// the returned stream is dropped and the relayed connection closes.
+fn relay_direct_tcpip(jump: Session, target_ip: IpAddr, target_port: u16) -> anyhow::Result<TcpStream> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| Error::JumpChannel(target_ip, target_port, e.to_string()))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| Error::JumpChannel(target_ip, target_port, e.to_string()))?;
+    let target_host = target_ip.to_string();
+
+    thread::spawn(move || {
+        let Ok((local, _)) = listener.accept() else { return };
+        let Ok(mut channel) = jump.channel_direct_tcpip(&target_host, target_port, None) else { return };
+        pump_duplex(&jump, &mut channel, local);
+    });
+
+    TcpStream::connect(local_addr).map_err(|e| Error::JumpChannel(target_ip, target_port, e.to_string()).into())
+}
+
+/// Shuttles bytes between `channel` and `local` until either side hits EOF or an error, putting
+/// `sess` in non-blocking mode so one stream being quiet can't stall the other (the same concern
+/// [`read_stdout_and_stderr`] handles for a command's stdout/stderr).
+fn pump_duplex(sess: &Session, channel: &mut ssh2::Channel, mut local: TcpStream) {
+    let _ = local.set_nonblocking(true);
+    let was_blocking = sess.is_blocking();
+    sess.set_blocking(false);
+    sess.set_timeout(POLL_TIMEOUT_MS);
+
+    let mut from_local = [0u8; CHUNK_SIZE];
+    let mut from_channel = [0u8; CHUNK_SIZE];
+    loop {
+        let mut made_progress = false;
+
+        match local.read(&mut from_local) {
+            Ok(0) => break,
+            Ok(n) => {
+                if channel.write_all(&from_local[..n]).is_err() {
+                    break;
+                }
+                made_progress = true;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(_) => break,
+        }
+
+        match channel.read(&mut from_channel) {
+            Ok(0) => break,
+            Ok(n) => {
+                if local.write_all(&from_channel[..n]).is_err() {
+                    break;
+                }
+                made_progress = true;
+            }
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+            Err(_) => break,
+        }
+
+        if !made_progress {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    sess.set_blocking(was_blocking);
+}
+
+/// One hop in a chain passed to [`create_session_chained`].
+pub struct Hop<'a> {
+    pub ip: IpAddr,
+    pub port: u16,
+    pub username: &'a str,
+    pub auth: Auth<'a>,
+    pub host_key_check: HostKeyCheck,
+}
+
+/// Builds a session to a host reached through zero or more bastions, each hop tunneled over the
+/// previous one via [`create_session_via`].
+///
+/// Each hop's own host key is verified against `~/.ssh/known_hosts` per its own
+/// [`Hop::host_key_check`], including the entry host — there is no blanket exemption for the
+/// first leg of the chain.
+///
+/// # Errors
+///
+/// This function will return an error as soon as any hop, starting with the entry host, fails
+/// host key verification, or fails to connect or authenticate.
+pub fn create_session_chained(entry_ip: IpAddr, entry_port: u16, entry_username: &str, entry_auth: Auth, entry_host_key_check: HostKeyCheck, hops: Vec<Hop>) -> anyhow::Result<Session> {
+    let mut current = create_session_with(entry_ip, entry_port, entry_username, entry_auth, entry_host_key_check)?;
+    for hop in hops {
+        current = create_session_via(&current, hop.ip, hop.port, hop.username, hop.auth, hop.host_key_check)?;
+    }
+    Ok(current)
+}
+
 /// Writes the specified content to a file on the remote host using the established SSH session.
 ///
 /// # Arguments
@@ -121,7 +417,7 @@ pub fn create_session(ip: IpAddr, port: u16, username: &str, privatekey: &Path)
 ///
 ///     let sess = create_session(ip, port, &username, &privatekey)?;
 ///     write_file(&sess, content, remote_file)?;
-///     run_commands(&sess, &["rm remote_file.txt"])?;
+///     run_commands(&sess, &["rm remote_file.txt"], true)?;
 ///
 ///     Ok(())
 /// }
@@ -131,10 +427,53 @@ pub fn create_session(ip: IpAddr, port: u16, username: &str, privatekey: &Path)
 ///
 /// This function does not explicitly panic.
 pub fn write_file(sess: &Session, content: &str, remote_file: &Path) -> anyhow::Result<()> {
-    let mut channel = sess.scp_send(remote_file, 0o644, content.len() as u64, None)
+    write_reader(sess, content.as_bytes(), content.len() as u64, remote_file, 0o644, None)
+}
+
+/// Streams bytes from `reader` to a file on the remote host over SCP, in bounded chunks rather
+/// than buffering the whole payload.
+///
+/// # Arguments
+///
+/// * `sess` - An active SSH session through which the file will be written.
+/// * `reader` - The source of the bytes to write.
+/// * `size` - The total number of bytes `reader` will yield; SCP requires this up front.
+/// * `remote_file` - The path on the remote host where the file will be created or overwritten.
+/// * `mode` - The Unix permission bits to create the remote file with.
+/// * `progress` - An optional callback invoked with `(bytes_transferred, size)` after each chunk.
+///
+/// # Errors
+///
+/// This function will return an error in the following cases:
+/// * If the creation of the remote file fails.
+/// * If reading from `reader` or writing to the remote file fails.
+/// * If any of the EOF or close operations on the SCP channel fail.
+pub fn write_reader(
+    sess: &Session,
+    mut reader: impl Read,
+    size: u64,
+    remote_file: &Path,
+    mode: i32,
+    mut progress: Option<&mut dyn FnMut(u64, u64)>,
+) -> anyhow::Result<()> {
+    let mut channel = sess.scp_send(remote_file, mode, size, None)
         .map_err(|e| Error::CreateRemoteFile(remote_file.into(), e.to_string()))?;
-    channel.write_all(content.as_bytes())
-        .map_err(|e| Error::WriteRemoteFile(remote_file.into(), e.to_string()))?;
+
+    let mut buf = [0u8; CHUNK_SIZE];
+    let mut transferred = 0u64;
+    loop {
+        let n = reader.read(&mut buf)
+            .map_err(|e| Error::WriteRemoteFile(remote_file.into(), e.to_string()))?;
+        if n == 0 {
+            break;
+        }
+        channel.write_all(&buf[..n])
+            .map_err(|e| Error::WriteRemoteFile(remote_file.into(), e.to_string()))?;
+        transferred += n as u64;
+        if let Some(progress) = progress.as_mut() {
+            progress(transferred, size);
+        }
+    }
     channel.send_eof()?;
     channel.wait_eof()?;
     channel.close()?;
@@ -185,7 +524,7 @@ pub fn write_file(sess: &Session, content: &str, remote_file: &Path) -> anyhow::
 ///
 ///     let content_read = read_file(&sess, &remote_file)?;
 ///     assert_eq!(content_written, content_read);
-///     run_commands(&sess, &["rm remote_file.txt"])?;
+///     run_commands(&sess, &["rm remote_file.txt"], true)?;
 ///
 ///     Ok(())
 /// }
@@ -201,42 +540,90 @@ pub fn write_file(sess: &Session, content: &str, remote_file: &Path) -> anyhow::
 /// * The function reads the entire contents of the remote file into a `String`.
 /// * Ensure that the remote file is accessible and readable by the SSH user.
 pub fn read_file(sess: &Session, remote_file: &Path) -> anyhow::Result<String> {
-    let (mut channel, _) = sess.scp_recv(remote_file)
-        .map_err(|e| Error::CreateRemoteFile(remote_file.into(), e.to_string()))?;
-    let mut data = String::new();
-    channel.read_to_string(&mut data)?;
-    Ok(data)
+    let mut data = Vec::new();
+    read_to_writer(sess, remote_file, &mut data, None)?;
+    Ok(String::from_utf8(data)?)
 }
 
-/// Executes a sequence of commands on a remote session and returns the combined output.
+/// Streams a remote file over SCP to `writer`, in bounded chunks rather than buffering the whole
+/// payload.
 ///
 /// # Arguments
 ///
 /// * `sess` - A reference to an established SSH `Session`.
-/// * `commands` - A slice of string slices representing the commands to be executed sequentially.
+/// * `remote_file` - The location of the remote file to be read.
+/// * `writer` - The destination the remote file's bytes are copied to.
+/// * `progress` - An optional callback invoked with `(bytes_transferred, total)` after each chunk.
 ///
-/// # Returns
+/// # Errors
 ///
-/// * `Ok(String)` - The combined standard output and standard error of the executed commands if they run successfully.
-/// * `Err(anyhow::Error)` - An error containing details if any step of the execution fails.
+/// This function will return an error in the following cases:
+/// * If there is an issue initiating the SCP receive session.
+/// * If there is an issue reading the data from the remote file or writing it to `writer`.
+pub fn read_to_writer(
+    sess: &Session,
+    remote_file: &Path,
+    mut writer: impl Write,
+    mut progress: Option<&mut dyn FnMut(u64, u64)>,
+) -> anyhow::Result<()> {
+    let (mut channel, stat) = sess.scp_recv(remote_file)
+        .map_err(|e| Error::CreateRemoteFile(remote_file.into(), e.to_string()))?;
+    let total = stat.size();
+
+    let mut buf = [0u8; CHUNK_SIZE];
+    let mut transferred = 0u64;
+    loop {
+        let n = channel.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n])?;
+        transferred += n as u64;
+        if let Some(progress) = progress.as_mut() {
+            progress(transferred, total);
+        }
+    }
+    Ok(())
+}
+
+/// The result of executing a single remote command, with stdout, stderr, and exit status kept
+/// separate so a failing command can be distinguished from a successful one.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_status: i32,
+    pub exit_signal: Option<String>,
+}
+
+impl CommandOutput {
+    /// Returns whether the command exited with status `0` and without a signal.
+    pub fn success(&self) -> bool {
+        self.exit_status == 0 && self.exit_signal.is_none()
+    }
+}
+
+/// Executes a single command on a remote session and returns its stdout, stderr, and exit status
+/// separately.
+///
+/// # Arguments
+///
+/// * `sess` - A reference to an established SSH `Session`.
+/// * `cmd` - The command to execute.
 ///
 /// # Errors
 ///
 /// This function will return an error in the following cases:
-///
 /// * If there is an issue creating the channel session.
-/// * If there is an issue executing the commands.
-/// * If there is an issue reading the output from the remote session.
-/// * If there is an issue closing the channel.
-///
-/// The error returned will include context about the specific step that failed.
+/// * If there is an issue executing the command.
+/// * If there is an issue reading stdout or stderr from the remote session.
+/// * If there is an issue waiting for the channel to close.
 ///
 /// # Example
 ///
 /// ```
-/// use std::net::{IpAddr, TcpStream};
+/// use std::net::IpAddr;
 /// use std::env;
-/// use ssh2::Session;
 /// use minimalist::ssh2::*;
 ///
 /// fn main() -> anyhow::Result<()> {
@@ -246,30 +633,159 @@ pub fn read_file(sess: &Session, remote_file: &Path) -> anyhow::Result<String> {
 ///     let privatekey = home::home_dir().unwrap().join(".ssh").join("id_rsa");
 ///     let sess = create_session(ip, port, &username, &privatekey)?;
 ///
-///     let commands = ["echo 'Hello, world!'", "uname -a"];
-///     let output = run_commands(&sess, &commands)?;
-///     println!("{}", output);
+///     let output = run_command(&sess, "echo 'Hello, world!'")?;
+///     assert!(output.success());
+///     println!("{}", output.stdout);
 ///
 ///     Ok(())
 /// }
 /// ```
+pub fn run_command(sess: &Session, cmd: &str) -> anyhow::Result<CommandOutput> {
+    let mut channel = sess.channel_session()?;
+    channel.exec(cmd)
+        .map_err(|e| Error::ExecCommands(cmd.to_string(), e.to_string()))?;
+
+    let (stdout, stderr) = read_stdout_and_stderr(sess, &mut channel, cmd)?;
+
+    channel.wait_close()?;
+    let exit_status = channel.exit_status()?;
+    let exit_signal = channel.exit_signal()?.exit_signal;
+
+    Ok(CommandOutput { stdout, stderr, exit_status, exit_signal })
+}
+
+/// How long a blocking read is allowed to wait for the session socket to become readable between
+/// drain attempts, once a pass over both streams has found nothing to read.
+const POLL_TIMEOUT_MS: u32 = 100;
+
+/// Drains a command channel's stdout and stderr together.
+///
+/// stdout and stderr share the channel's flow-control window, so reading one to EOF before
+/// touching the other can deadlock a command that writes enough to the second stream while the
+/// first is left unread. This puts the session in non-blocking mode and alternates small reads
+/// between the two streams until both report EOF, restoring the session's prior blocking mode
+/// before returning (including on error). When a pass over both streams finds nothing to read, it
+/// switches to a bounded blocking read so the thread actually sleeps on the socket instead of
+/// spinning, rather than retrying the non-blocking read in a tight loop.
+fn read_stdout_and_stderr(sess: &Session, channel: &mut ssh2::Channel, cmd: &str) -> anyhow::Result<(String, String)> {
+    let was_blocking = sess.is_blocking();
+    sess.set_blocking(false);
+    sess.set_timeout(POLL_TIMEOUT_MS);
+    let result = (|| {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut buf = [0u8; CHUNK_SIZE];
+        let mut stdout_done = false;
+        let mut stderr_done = false;
+
+        while !stdout_done || !stderr_done {
+            let mut made_progress = false;
+
+            if !stdout_done {
+                match channel.read(&mut buf) {
+                    Ok(0) => {
+                        stdout_done = true;
+                        made_progress = true;
+                    }
+                    Ok(n) => {
+                        stdout.extend_from_slice(&buf[..n]);
+                        made_progress = true;
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                    Err(e) => return Err(Error::ExecCommands(cmd.to_string(), e.to_string()).into()),
+                }
+            }
+            if !stderr_done {
+                match channel.stderr().read(&mut buf) {
+                    Ok(0) => {
+                        stderr_done = true;
+                        made_progress = true;
+                    }
+                    Ok(n) => {
+                        stderr.extend_from_slice(&buf[..n]);
+                        made_progress = true;
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                    Err(e) => return Err(Error::ExecCommands(cmd.to_string(), e.to_string()).into()),
+                }
+            }
+
+            // Neither stream had anything ready this pass. Rather than spin the non-blocking
+            // read, block on whichever stream is still open for up to `POLL_TIMEOUT_MS`, which
+            // lets the thread actually sleep on the socket until data (or the timeout) arrives.
+            if !made_progress && (!stdout_done || !stderr_done) {
+                sess.set_blocking(true);
+                let block_result = if !stdout_done {
+                    channel.read(&mut buf).map(|n| (false, n))
+                } else {
+                    channel.stderr().read(&mut buf).map(|n| (true, n))
+                };
+                sess.set_blocking(false);
+                match block_result {
+                    Ok((true, 0)) => stderr_done = true,
+                    Ok((false, 0)) => stdout_done = true,
+                    Ok((true, n)) => stderr.extend_from_slice(&buf[..n]),
+                    Ok((false, n)) => stdout.extend_from_slice(&buf[..n]),
+                    Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+                    Err(e) => return Err(Error::ExecCommands(cmd.to_string(), e.to_string()).into()),
+                }
+            }
+        }
+
+        Ok((String::from_utf8(stdout)?, String::from_utf8(stderr)?))
+    })();
+    sess.set_blocking(was_blocking);
+    sess.set_timeout(0);
+    result
+}
+
+/// Executes a slice of commands on a remote session sequentially, one [`run_command`] call per
+/// entry, and collects each step's result.
 ///
-/// # Dependencies
+/// # Arguments
 ///
-/// This function depends on the `ssh2` crate for managing the SSH session and channels, and `anyhow` crate for error handling.
+/// * `sess` - A reference to an established SSH `Session`.
+/// * `commands` - The commands to execute, in order.
+/// * `stop_on_error` - If `true`, stop after the first command whose [`CommandOutput::success`]
+///   is `false`, returning the results gathered so far.
 ///
-/// # Note
+/// # Errors
 ///
-/// * Make sure that the `Session` object is properly authenticated before calling this function.
-/// * The commands are joined using a semicolon (`;`), which means they will be executed in sequence within a single shell session.
-/// * The function captures both standard output and standard error combined in the returned string.
-pub fn run_commands(sess: &Session, commands: &[&str]) -> anyhow::Result<String> {
-    let mut channel = sess.channel_session()?;
-    let joined_comand = commands.join(";");
-    channel.exec(&joined_comand)
-        .map_err(|e| Error::ExecCommands(joined_comand, e.to_string()))?;
-    let mut s = String::new();
-    channel.read_to_string(&mut s)?;
-    channel.wait_close()?;
-    Ok(s)
+/// This function returns an error under the same conditions as [`run_command`]; a command that
+/// runs but exits non-zero is reported via `CommandOutput`, not as an `Err`.
+///
+/// # Example
+///
+/// ```
+/// use std::net::IpAddr;
+/// use std::env;
+/// use minimalist::ssh2::*;
+///
+/// fn main() -> anyhow::Result<()> {
+///     let ip: IpAddr = "127.0.0.1".parse().unwrap();
+///     let port: u16 = 22;
+///     let username = env::var("LOCAL_SSH_USERNAME").unwrap();
+///     let privatekey = home::home_dir().unwrap().join(".ssh").join("id_rsa");
+///     let sess = create_session(ip, port, &username, &privatekey)?;
+///
+///     let commands = ["echo 'Hello, world!'", "uname -a"];
+///     let outputs = run_commands(&sess, &commands, true)?;
+///     for output in &outputs {
+///         println!("{}", output.stdout);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub fn run_commands(sess: &Session, commands: &[&str], stop_on_error: bool) -> anyhow::Result<Vec<CommandOutput>> {
+    let mut results = Vec::with_capacity(commands.len());
+    for cmd in commands {
+        let output = run_command(sess, cmd)?;
+        let failed = !output.success();
+        results.push(output);
+        if failed && stop_on_error {
+            break;
+        }
+    }
+    Ok(results)
 }